@@ -143,9 +143,169 @@
 //!
 
 extern crate rand;
+extern crate rand_distr;
+extern crate rayon;
 
 use rand::{distributions::Uniform, prelude::*};
+use rand_distr::{Cauchy, Normal};
 use rand_xorshift::XorShiftRng;
+use rayon::prelude::*;
+
+/// Selects which differential evolution mutation scheme `update_positions`
+/// uses to build the mutant vector for each individual. `x_r1`, `x_r2`, ...
+/// denote the personal-best positions of distinct, randomly chosen
+/// individuals, and `x_best` the current global best position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// DE/rand/1: `x_r3 + F*(x_r1 - x_r2)`. The classic, most widely used
+    /// DE variant, and the default.
+    Rand1,
+
+    /// DE/best/1: like `Rand1`, but the base vector is the global best
+    /// individual instead of a third random individual.
+    Best1,
+
+    /// DE/rand/2: `x_r1 + F*(x_r2 - x_r3) + F*(x_r4 - x_r5)`, sampling five
+    /// distinct individuals.
+    Rand2,
+
+    /// DE/current-to-best/1: `x_i + F*(x_best - x_i) + F*(x_r1 - x_r2)`.
+    CurrentToBest1,
+}
+
+/// Selects how the mutant vector produced by `Strategy` is combined with an
+/// individual's own personal best to form the trial vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crossover {
+    /// Binomial crossover: each dimension independently takes the mutant
+    /// value with probability `cr`, except for one forced dimension that
+    /// always does, guaranteeing at least one changed dimension.
+    Binomial,
+
+    /// Exponential crossover: starting from a random dimension, copies
+    /// consecutive (wrapping) dimensions from the mutant for as long as a
+    /// `cr` coin flip keeps succeeding; at least the starting dimension is
+    /// always copied. All other dimensions keep the personal best.
+    Exponential,
+}
+
+/// Selects how an individual's `cr`/`f` control parameters self-adapt
+/// across generations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adaptation {
+    /// jDE: each generation, `cr`/`f` are either resampled uniformly from
+    /// `cr_min_max`/`f_min_max` (with probability `cr_change_probability`/
+    /// `f_change_probability`) or inherited from the individual's personal
+    /// best. This is the default, and matches earlier versions of this
+    /// crate.
+    Jde,
+
+    /// JADE: `cr`/`f` are drawn each generation from adaptive
+    /// distributions centered on population-wide means `mu_cr`/`mu_f`,
+    /// which are nudged towards the `cr`/`f` of individuals that improved
+    /// on their parent. Mutation uses DE/current-to-pbest/1 with an
+    /// external archive of recently displaced parents. This setting
+    /// overrides `strategy` and `crossover`, which apply only to `Jde`.
+    /// See "JADE: Adaptive Differential Evolution With Optional External
+    /// Archive" (Zhang & Sanderson, 2009).
+    Jade {
+        /// Fraction of the population, ranked by cost, that `x_pbest` is
+        /// drawn from. A common choice is 0.1.
+        p: f32,
+        /// Adaptation rate for `mu_cr`/`mu_f` towards the generation's
+        /// successful `cr`/`f` values. A common choice is 0.1.
+        c: f32,
+    },
+}
+
+/// Selects how an out-of-bounds component produced by mutation is handled
+/// against `min_max_pos`. Applied to every dimension a crossover writes a
+/// mutated value into, in `update_positions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundHandling {
+    /// No constraint: components may leave `min_max_pos` freely. This is
+    /// the default, matching the behavior of earlier versions of this
+    /// crate, which explicitly documents that the DE "will search outside
+    /// of this initial search space."
+    None,
+
+    /// Snaps an out-of-bounds component to the nearest edge.
+    Clamp,
+
+    /// Mirrors an overshoot back inside the interval, iterating if the
+    /// reflection itself overshoots the far side.
+    Reflect,
+
+    /// Redraws an out-of-bounds component uniformly within bounds.
+    Reinitialize,
+
+    /// Maps an out-of-bounds component modulo the interval width.
+    Wrap,
+}
+
+// Applies `handling` to a single mutated component `x`, against the bounds
+// `(lo, hi)` for its dimension.
+fn apply_bound_handling<R: rand::Rng>(
+    handling: BoundHandling,
+    (lo, hi): (f32, f32),
+    x: f32,
+    rng: &mut R,
+) -> f32 {
+    match handling {
+        BoundHandling::None => x,
+        BoundHandling::Clamp => x.max(lo).min(hi),
+        BoundHandling::Reflect => {
+            let mut x = x;
+            while x < lo || x > hi {
+                if x < lo {
+                    x = lo + (lo - x);
+                } else if x > hi {
+                    x = hi - (x - hi);
+                }
+            }
+            x
+        }
+        BoundHandling::Wrap => {
+            let width = hi - lo;
+            let mut x = x;
+            while x < lo {
+                x += width;
+            }
+            while x > hi {
+                x -= width;
+            }
+            x
+        }
+        BoundHandling::Reinitialize => {
+            if x < lo || x > hi {
+                Uniform::new(lo, hi).sample(rng)
+            } else {
+                x
+            }
+        }
+    }
+}
+
+/// A predicate used by `RestartConfig::has_improved`.
+type ImprovedPredicate<C> = dyn Fn(&C, &C) -> bool;
+
+/// Stagnation-detection and restart settings. See `Settings::restart`.
+pub struct RestartConfig<C> {
+    /// Called once per generation with `(previous_best, current_best)`,
+    /// where `previous_best` is the global best cost at the first check,
+    /// the last time this returned `true`, or the last restart, whichever
+    /// came latest. Should return `true` if `current_best`
+    /// improves on `previous_best` by enough to not count as stagnation.
+    /// Left to the caller rather than requiring `C: Sub` so that this
+    /// crate keeps working for any `C: PartialOrd + Clone` cost type; for
+    /// a numeric cost and a fixed tolerance `tol`, a typical choice is
+    /// `Box::new(move |prev, curr| *prev - *curr > tol)`.
+    pub has_improved: Box<ImprovedPredicate<C>>,
+
+    /// Number of consecutive generations `has_improved` must return
+    /// `false` in a row before a restart triggers.
+    pub stall_generations: usize,
+}
 
 /// Holds all settings for the self adaptive differential evolution
 /// algorithm.
@@ -188,6 +348,38 @@ where
     /// between 20 and 200.
     pub pop_size: usize,
 
+    /// Mutation scheme used to build each individual's mutant vector.
+    /// Defaults to `Strategy::Rand1`, i.e. DE/rand/1, which matches the
+    /// behavior of earlier versions of this crate. Separable problems
+    /// often do better with `Rand1`/`Rand2`, while `Best1` and
+    /// `CurrentToBest1` converge faster on problems where exploiting the
+    /// current best helps more than it hurts diversity.
+    pub strategy: Strategy,
+
+    /// Crossover scheme used to combine the mutant vector with an
+    /// individual's personal best. Defaults to `Crossover::Binomial`,
+    /// matching the behavior of earlier versions of this crate.
+    pub crossover: Crossover,
+
+    /// Self-adaptation scheme for `cr`/`f`. Defaults to `Adaptation::Jde`,
+    /// matching the behavior of earlier versions of this crate.
+    pub adaptation: Adaptation,
+
+    /// How mutated components that fall outside `min_max_pos` are handled.
+    /// Defaults to `BoundHandling::None`, matching the behavior of earlier
+    /// versions of this crate, which let the DE search outside the initial
+    /// search space freely.
+    pub bound_handling: BoundHandling,
+
+    /// Stagnation-detection and restart settings. When `Some`, if the
+    /// global best cost fails `RestartConfig::has_improved` for
+    /// `stall_generations` consecutive generations, every individual
+    /// except the single global best is reinitialized with a fresh
+    /// uniform position (and resampled `cr`/`f`). Defaults to `None`,
+    /// matching the behavior of earlier versions of this crate, which
+    /// never restarts.
+    pub restart: Option<RestartConfig<C>>,
+
     /// Random number generator used to generate mutations. If the fitness
     /// function is fairly fast, the random number generator should be
     /// very fast as well. Since it is not necessary to use a cryptographic
@@ -224,6 +416,13 @@ where
             f_change_probability: 0.1,
 
             pop_size: 100,
+
+            strategy: Strategy::Rand1,
+            crossover: Crossover::Binomial,
+            adaptation: Adaptation::Jde,
+            bound_handling: BoundHandling::None,
+            restart: None,
+
             rng: XorShiftRng::seed_from_u64(2),
 
             cost_function: cost_function,
@@ -272,6 +471,23 @@ where
     between_f: Uniform<f32>,
 
     pop_countdown: usize,
+
+    // JADE adaptive means for cr/f, only used when `settings.adaptation`
+    // is `Adaptation::Jade`.
+    mu_cr: f32,
+    mu_f: f32,
+
+    // JADE external archive of recently displaced parent positions.
+    archive: Vec<Vec<f32>>,
+
+    // global best cost at the first stagnation check, the last time
+    // `has_improved` returned `true`, or the last restart, and how many
+    // consecutive generations `settings.restart`'s `has_improved` has
+    // returned `false` in a row. Only used when `settings.restart` is
+    // `Some`.
+    restart_best_cost: Option<C>,
+    stall_count: usize,
+    num_restarts: usize,
 }
 
 /// Convenience function to create a fully configured self adaptive
@@ -300,6 +516,22 @@ where
             "need at least one element to optimize"
         );
 
+        // `update_positions_jde` rejection-samples this many distinct
+        // individuals per trial; with fewer individuals than that in the
+        // population, it would loop forever.
+        let min_pop_size = match s.strategy {
+            Strategy::Best1 | Strategy::CurrentToBest1 => 2,
+            Strategy::Rand1 => 3,
+            Strategy::Rand2 => 5,
+        };
+        assert!(
+            s.pop_size >= min_pop_size,
+            "pop_size must be at least {} to use Strategy::{:?}, got {}",
+            min_pop_size,
+            s.strategy,
+            s.pop_size
+        );
+
         // create a vector of randomly initialized individuals for current.
         let dim = s.min_max_pos.len();
 
@@ -324,6 +556,12 @@ where
             between_dim: Uniform::new(0, dim),
             between_cr: Uniform::new(s.cr_min_max.0, s.cr_min_max.1),
             between_f: Uniform::new(s.f_min_max.0, s.f_min_max.1),
+            mu_cr: 0.5,
+            mu_f: 0.5,
+            archive: Vec::new(),
+            restart_best_cost: None,
+            stall_count: 0,
+            num_restarts: 0,
             settings: s,
         };
 
@@ -343,8 +581,14 @@ where
         pop
     }
 
-    /// Loops through each individual and updates its personal best.
-    fn update_best(&mut self) {
+    /// Loops through each individual and updates its personal best. Returns,
+    /// for each individual, whether its trial improved on (or matched) its
+    /// personal best this generation -- used by `Adaptation::Jade` to build
+    /// its success sets and external archive. A slot whose personal best
+    /// has no cost yet (the first generation, or after a restart) is
+    /// swapped in but not counted.
+    fn update_best(&mut self) -> Vec<bool> {
+        let mut improved = vec![false; self.curr.len()];
         for i in 0..self.curr.len() {
             let curr = &mut self.curr[i];
             let best = &mut self.best[i];
@@ -359,31 +603,54 @@ where
                     }
                 }
             }
+            improved[i] = is_swapping && best.cost.is_some();
 
             if is_swapping {
                 // replace individual's best. swap is *much* faster than clone.
                 std::mem::swap(curr, best);
             }
         }
+        improved
+    }
+
+    // Modifies all the curr positions, dispatching to the mutation/crossover
+    // or self-adaptation scheme selected by `settings.adaptation`.
+    fn update_positions(&mut self, improved: &[bool]) {
+        match self.settings.adaptation {
+            Adaptation::Jde => self.update_positions_jde(),
+            Adaptation::Jade { p, c } => self.update_positions_jade(p, c, improved),
+        }
     }
 
-    // Modifies all the curr positions. This needs a lot of random numbers, so
-    // for a fast cost function it is important to use a fast random number
+    // Modifies all the curr positions using the `Strategy`/`Crossover`
+    // chosen in `settings`. This needs a lot of random numbers, so for a
+    // fast cost function it is important to use a fast random number
     // generator.
-    fn update_positions(&mut self) {
-        let rng = &mut self.settings.rng;
-        for i in 0..self.curr.len() {
-            // sample 3 different individuals
-            let id1 = self.between_popsize.sample(rng);
+    fn update_positions_jde(&mut self) {
+        // the global best position, needed by the Best1/CurrentToBest1
+        // strategies. Cloned up front since it has to be read through `&self`
+        // before the per-individual loop starts mutating `curr`/`best`.
+        let global_best_pos = self.best().map(|(_, pos)| pos.to_vec());
 
-            let mut id2 = self.between_popsize.sample(rng);
-            while id2 == id1 {
-                id2 = self.between_popsize.sample(rng);
-            }
+        let strategy = self.settings.strategy;
+        let crossover = self.settings.crossover;
+        let bound_handling = self.settings.bound_handling;
+        let dim = self.dim;
 
-            let mut id3 = self.between_popsize.sample(rng);
-            while id3 == id1 || id3 == id2 {
-                id3 = self.between_popsize.sample(rng);
+        let rng = &mut self.settings.rng;
+        for i in 0..self.curr.len() {
+            // sample as many distinct individuals as the strategy needs
+            let n_ids = match strategy {
+                Strategy::Rand1 => 3,
+                Strategy::Best1 | Strategy::CurrentToBest1 => 2,
+                Strategy::Rand2 => 5,
+            };
+            let mut ids = Vec::with_capacity(n_ids);
+            while ids.len() < n_ids {
+                let candidate = self.between_popsize.sample(rng);
+                if !ids.contains(&candidate) {
+                    ids.push(candidate);
+                }
             }
 
             let curr = &mut self.curr[i];
@@ -402,26 +669,214 @@ where
                 curr.f = best.f;
             }
 
-            let curr_pos = &mut curr.pos;
-            let best_pos = &best.pos;
-            let best1_pos = &self.best[id1].pos;
-            let best2_pos = &self.best[id2].pos;
-            let best3_pos = &self.best[id3].pos;
+            // build the mutant vector according to the chosen strategy. See
+            // "A Comparative Study of Differential Evolution Variants for
+            // Global Optimization (2006)".
+            let mut mutant = vec![0.0f32; dim];
+            match strategy {
+                Strategy::Rand1 => {
+                    let (r1_pos, r2_pos, r3_pos) = (
+                        &self.best[ids[0]].pos,
+                        &self.best[ids[1]].pos,
+                        &self.best[ids[2]].pos,
+                    );
+                    for d in 0..dim {
+                        mutant[d] = r3_pos[d] + curr.f * (r1_pos[d] - r2_pos[d]);
+                    }
+                }
+                Strategy::Best1 => {
+                    let base_pos = global_best_pos.as_deref().unwrap_or(&best.pos);
+                    let (r1_pos, r2_pos) = (&self.best[ids[0]].pos, &self.best[ids[1]].pos);
+                    for d in 0..dim {
+                        mutant[d] = base_pos[d] + curr.f * (r1_pos[d] - r2_pos[d]);
+                    }
+                }
+                Strategy::Rand2 => {
+                    let (r1_pos, r2_pos, r3_pos, r4_pos, r5_pos) = (
+                        &self.best[ids[0]].pos,
+                        &self.best[ids[1]].pos,
+                        &self.best[ids[2]].pos,
+                        &self.best[ids[3]].pos,
+                        &self.best[ids[4]].pos,
+                    );
+                    for d in 0..dim {
+                        mutant[d] = r1_pos[d]
+                            + curr.f * (r2_pos[d] - r3_pos[d])
+                            + curr.f * (r4_pos[d] - r5_pos[d]);
+                    }
+                }
+                Strategy::CurrentToBest1 => {
+                    let base_pos = global_best_pos.as_deref().unwrap_or(&best.pos);
+                    let (r1_pos, r2_pos) = (&self.best[ids[0]].pos, &self.best[ids[1]].pos);
+                    for d in 0..dim {
+                        mutant[d] = best.pos[d]
+                            + curr.f * (base_pos[d] - best.pos[d])
+                            + curr.f * (r1_pos[d] - r2_pos[d]);
+                    }
+                }
+            }
 
-            let forced_mutation_dim = self.between_dim.sample(rng);
+            // start from the personal best, then overwrite the dimensions
+            // the crossover selects with the mutant's values.
+            curr.pos.copy_from_slice(&best.pos);
+            match crossover {
+                Crossover::Binomial => {
+                    let forced_mutation_dim = self.between_dim.sample(rng);
+                    for (d, &m) in mutant.iter().enumerate() {
+                        if d == forced_mutation_dim || rng.gen::<f32>() < curr.cr {
+                            curr.pos[d] = apply_bound_handling(
+                                bound_handling,
+                                self.settings.min_max_pos[d],
+                                m,
+                                rng,
+                            );
+                        }
+                    }
+                }
+                Crossover::Exponential => {
+                    let start = self.between_dim.sample(rng);
+                    let mut d = start;
+                    loop {
+                        curr.pos[d] = apply_bound_handling(
+                            bound_handling,
+                            self.settings.min_max_pos[d],
+                            mutant[d],
+                            rng,
+                        );
+                        d = (d + 1) % dim;
+                        if d == start || rng.gen::<f32>() >= curr.cr {
+                            break;
+                        }
+                    }
+                }
+            }
 
-            // This implements the DE/rand/1/bin, the most widely used algorithm.
-            // See "A Comparative Study of Differential Evolution Variants for
-            // Global Optimization (2006)".
-            for d in 0..self.dim {
-                if d == forced_mutation_dim || rng.gen::<f32>() < curr.cr {
-                    curr_pos[d] = best3_pos[d] + curr.f * (best1_pos[d] - best2_pos[d]);
+            // reset cost, has to be updated by the user.
+            curr.cost = None;
+        }
+    }
+
+    // Modifies all the curr positions using JADE: adaptive `cr`/`f` sampled
+    // from `mu_cr`/`mu_f`, and DE/current-to-pbest/1 mutation with an
+    // external archive of displaced parents. `improved` marks which
+    // individuals' trials succeeded last generation, i.e. which `self.best`
+    // entries now hold the cr/f that should feed into the success sets, and
+    // which `self.curr` entries now hold a just-displaced parent.
+    fn update_positions_jade(&mut self, p: f32, c: f32, improved: &[bool]) {
+        let dim = self.dim;
+        let pop_size = self.curr.len();
+
+        // collect this generation's success sets before anything below
+        // overwrites `curr`, and archive the parents they displaced.
+        let mut success_cr = Vec::new();
+        let mut success_f = Vec::new();
+        for (i, &did_improve) in improved.iter().enumerate() {
+            if did_improve {
+                success_cr.push(self.best[i].cr);
+                success_f.push(self.best[i].f);
+                self.archive.push(self.curr[i].pos.clone());
+            }
+        }
+        while self.archive.len() > pop_size {
+            let victim = Uniform::new(0, self.archive.len()).sample(&mut self.settings.rng);
+            self.archive.swap_remove(victim);
+        }
+
+        if !success_cr.is_empty() {
+            let mean_cr = success_cr.iter().sum::<f32>() / success_cr.len() as f32;
+            self.mu_cr = (1.0 - c) * self.mu_cr + c * mean_cr;
+        }
+        if !success_f.is_empty() {
+            let sum_f: f32 = success_f.iter().sum();
+            let sum_f_sq: f32 = success_f.iter().map(|f| f * f).sum();
+            let lehmer_mean_f = sum_f_sq / sum_f;
+            self.mu_f = (1.0 - c) * self.mu_f + c * lehmer_mean_f;
+        }
+
+        // rank the population (now living in `self.best`, after the swap in
+        // `update_best`) by cost, to find the top-`p` fraction `x_pbest` is
+        // drawn from.
+        let mut ranked: Vec<usize> = (0..pop_size)
+            .filter(|&i| self.best[i].cost.is_some())
+            .collect();
+        ranked.sort_by(|&a, &b| {
+            self.best[a]
+                .cost
+                .as_ref()
+                .unwrap()
+                .partial_cmp(self.best[b].cost.as_ref().unwrap())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let n_pbest = ((p * pop_size as f32).ceil() as usize)
+            .max(1)
+            .min(ranked.len().max(1));
+
+        let normal_cr = Normal::new(self.mu_cr, 0.1).unwrap();
+        let cauchy_f = Cauchy::new(self.mu_f, 0.1).unwrap();
+        let between_pbest = Uniform::new(0, n_pbest);
+        let between_pop = Uniform::new(0, pop_size);
+        let between_pool = Uniform::new(0, pop_size + self.archive.len());
+        let bound_handling = self.settings.bound_handling;
+
+        for i in 0..pop_size {
+            let rng = &mut self.settings.rng;
+
+            // self-adaptive cr/f for this individual's trial
+            let cr_i = normal_cr.sample(rng).clamp(0.0, 1.0);
+            let mut f_i = cauchy_f.sample(rng);
+            while f_i <= 0.0 {
+                f_i = cauchy_f.sample(rng);
+            }
+            if f_i > 1.0 {
+                f_i = 1.0;
+            }
+
+            let pbest_idx = ranked[between_pbest.sample(rng)];
+
+            let mut r1 = between_pop.sample(rng);
+            while r1 == i {
+                r1 = between_pop.sample(rng);
+            }
+
+            // r2 is drawn from the union of the population and the archive.
+            let r2 = loop {
+                let candidate = between_pool.sample(rng);
+                if candidate < pop_size {
+                    if candidate != i && candidate != r1 {
+                        break candidate;
+                    }
                 } else {
-                    curr_pos[d] = best_pos[d];
+                    break candidate;
+                }
+            };
+
+            let curr = &mut self.curr[i];
+            let x_i = &self.best[i].pos;
+            let x_pbest = &self.best[pbest_idx].pos;
+            let x_r1 = &self.best[r1].pos;
+            let x_r2: &[f32] = if r2 < pop_size {
+                &self.best[r2].pos
+            } else {
+                &self.archive[r2 - pop_size]
+            };
+
+            let mut mutant = vec![0.0f32; dim];
+            for d in 0..dim {
+                mutant[d] = x_i[d] + f_i * (x_pbest[d] - x_i[d]) + f_i * (x_r1[d] - x_r2[d]);
+            }
+
+            // binomial crossover against the current population member.
+            curr.pos.copy_from_slice(x_i);
+            let forced_mutation_dim = self.between_dim.sample(rng);
+            for (d, &m) in mutant.iter().enumerate() {
+                if d == forced_mutation_dim || rng.gen::<f32>() < cr_i {
+                    curr.pos[d] =
+                        apply_bound_handling(bound_handling, self.settings.min_max_pos[d], m, rng);
                 }
             }
 
-            // reset cost, has to be updated by the user.
+            curr.cr = cr_i;
+            curr.f = f_i;
             curr.cost = None;
         }
     }
@@ -452,6 +907,79 @@ where
         self.num_cost_evaluations
     }
 
+    /// Gets the number of times stagnation detection has triggered a
+    /// population restart. Always `0` unless `settings.restart` is `Some`.
+    pub fn num_restarts(&self) -> usize {
+        self.num_restarts
+    }
+
+    // Returns the authoritative individual for slot `i`: `self.best[i]` while
+    // it has a cost, falling back to `self.curr[i]` otherwise. That is the
+    // case before the first generation boundary has run `update_best` (at
+    // which point `self.best[i]` is still the all-zero, never-evaluated dummy
+    // individual `Population::new` fills it with), and after
+    // `restart_population` has cleared `self.best[i]`'s cost.
+    fn representative(&self, i: usize) -> &Individual<C> {
+        if self.best[i].cost.is_some() {
+            &self.best[i]
+        } else {
+            &self.curr[i]
+        }
+    }
+
+    /// Gets an iterator over `(cost, position, cr, f)` for every individual
+    /// in the current population, in slot order. `cost` is `None` until
+    /// that individual's position has been evaluated at least once. Useful
+    /// for plotting convergence or driving custom, adaptive stopping
+    /// criteria.
+    pub fn individuals(&self) -> impl Iterator<Item = (Option<&C>, &[f32], f32, f32)> {
+        (0..self.best.len()).map(move |i| {
+            let ind = self.representative(i);
+            (ind.cost.as_ref(), ind.pos.as_slice(), ind.cr, ind.f)
+        })
+    }
+
+    /// Computes the mean Euclidean distance of each individual's position
+    /// to the population centroid -- a simple diversity measure. A value
+    /// near zero means the population has collapsed around a point, which
+    /// together with a stalled `best()` cost suggests premature
+    /// convergence.
+    pub fn diversity(&self) -> f32 {
+        let dim = self.dim;
+        let n = self.best.len() as f32;
+
+        let mut centroid = vec![0.0f32; dim];
+        for i in 0..self.best.len() {
+            let pos = &self.representative(i).pos;
+            for d in 0..dim {
+                centroid[d] += pos[d];
+            }
+        }
+        for c in &mut centroid {
+            *c /= n;
+        }
+
+        let sum_dist: f32 = (0..self.best.len())
+            .map(|i| {
+                self.representative(i)
+                    .pos
+                    .iter()
+                    .zip(&centroid)
+                    .map(|(x, c)| (x - c).powi(2))
+                    .sum::<f32>()
+                    .sqrt()
+            })
+            .sum();
+
+        sum_dist / n
+    }
+
+    /// Gets an iterator for this population. Each call to `next()`
+    /// performs one cost evaluation.
+    pub fn iter(&mut self) -> PopIter<F, R, C> {
+        PopIter { pop: self }
+    }
+
     /// Performs a single cost evaluation, and updates best positions and
     /// evolves the population if the whole population has been evaluated.
     /// Returns the cost value of the current best solution found.
@@ -459,8 +987,9 @@ where
         if 0 == self.pop_countdown {
             // if the whole pop has been evaluated, evolve it to update positions.
             // this also copies curr to best, if better.
-            self.update_best();
-            self.update_positions();
+            let improved = self.update_best();
+            self.update_positions(&improved);
+            self.maybe_restart();
             self.pop_countdown = self.curr.len();
         }
 
@@ -481,10 +1010,129 @@ where
         }
     }
 
-    /// Gets an iterator for this population. Each call to `next()`
-    /// performs one cost evaluation.
-    pub fn iter(&mut self) -> PopIter<F, R, C> {
-        PopIter { pop: self }
+    // Checks whether the global best cost has stagnated for
+    // `settings.restart`'s `stall_generations`, and if so, reinitializes
+    // every individual but the single global best.
+    fn maybe_restart(&mut self) {
+        let restart = match &self.settings.restart {
+            Some(r) => r,
+            None => return,
+        };
+        let stall_generations = restart.stall_generations;
+        let current_best = match &self.best_cost_cache {
+            Some(c) => c.clone(),
+            None => return,
+        };
+
+        let improved = match &self.restart_best_cost {
+            Some(prev) => (restart.has_improved)(prev, &current_best),
+            None => true,
+        };
+
+        if improved {
+            self.restart_best_cost = Some(current_best);
+            self.stall_count = 0;
+        } else {
+            self.stall_count += 1;
+            if self.stall_count >= stall_generations {
+                self.restart_population();
+                self.restart_best_cost = Some(current_best);
+                self.stall_count = 0;
+                self.num_restarts += 1;
+            }
+        }
+    }
+
+    // Reinitializes every individual except the single global best with a
+    // fresh uniform position and resampled `cr`/`f`, and resets its cost to
+    // `None`.
+    fn restart_population(&mut self) {
+        let global_best_idx = match self.best_idx {
+            Some(i) => i,
+            None => return,
+        };
+
+        for i in 0..self.curr.len() {
+            if i == global_best_idx {
+                continue;
+            }
+
+            self.curr[i].cr = self.between_cr.sample(&mut self.settings.rng);
+            self.curr[i].f = self.between_f.sample(&mut self.settings.rng);
+            for d in 0..self.dim {
+                let between_min_max = Uniform::new(
+                    self.settings.min_max_pos[d].0,
+                    self.settings.min_max_pos[d].1,
+                );
+                self.curr[i].pos[d] = between_min_max.sample(&mut self.settings.rng);
+            }
+            self.curr[i].cost = None;
+            self.best[i].cost = None;
+        }
+
+        // under `Adaptation::Jade`, the archive holds parent positions from
+        // before the restart; keeping them around would let `x_r2` keep
+        // drawing from the stale population long after a fresh start.
+        if let Adaptation::Jade { .. } = self.settings.adaptation {
+            self.archive.clear();
+        }
+    }
+}
+
+impl<F, R, C> Population<F, R, C>
+where
+    F: Fn(&[f32]) -> C + Sync,
+    R: rand::Rng,
+    C: PartialOrd + Clone + Send + Sync,
+{
+    /// Returns a rayon parallel iterator over the position vectors of the
+    /// current generation. Useful for callers who want to drive their own
+    /// batched cost evaluation instead of going through `eval_generation`.
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = &[f32]> {
+        self.curr.par_iter().map(|ind| ind.pos.as_slice())
+    }
+
+    /// Evaluates every individual of the current generation whose cost is
+    /// still `None` concurrently, using rayon, then evolves the population
+    /// once the whole generation has been scored.
+    ///
+    /// Unlike `eval`, which performs exactly one cost evaluation per call,
+    /// this advances a full generation per call. For costly, embarrassingly
+    /// parallel cost functions (e.g. physical simulations) this gives
+    /// near-linear speedups over the serial `eval`/`iter` API, which is why
+    /// it requires `F: Sync` and `C: Send + Sync` while the serial path does
+    /// not.
+    pub fn eval_generation(&mut self) {
+        let cost_function = &self.settings.cost_function;
+
+        let results: Vec<Option<C>> = self
+            .curr
+            .par_iter()
+            .map(|ind| ind.cost.is_none().then(|| cost_function(&ind.pos)))
+            .collect();
+
+        let mut n_evaluated = 0;
+        for (i, result) in results.into_iter().enumerate() {
+            if let Some(cost) = result {
+                self.curr[i].cost = Some(cost);
+                n_evaluated += 1;
+
+                // see if we have improved the global best
+                if self.best_cost_cache.is_none()
+                    || self.curr[i].cost.as_ref().unwrap() < self.best_cost_cache.as_ref().unwrap()
+                {
+                    self.best_cost_cache = self.curr[i].cost.clone();
+                    self.best_idx = Some(i);
+                }
+            }
+        }
+        self.num_cost_evaluations += n_evaluated;
+
+        // the whole generation has been evaluated, so evolve it.
+        let improved = self.update_best();
+        self.update_positions(&improved);
+        self.maybe_restart();
+        self.pop_countdown = self.curr.len();
     }
 }
 
@@ -516,5 +1164,200 @@ where
 
 #[cfg(test)]
 mod tests {
-    // TODO
+    use super::*;
+
+    // sphere function: a simple, separable, convex cost with a single
+    // minimum of 0 at the origin. Used throughout these tests as a cheap
+    // sanity check that the optimizer actually makes progress.
+    fn sphere(pos: &[f32]) -> f32 {
+        pos.iter().map(|x| x * x).sum()
+    }
+
+    #[test]
+    fn eval_generation_evaluates_every_individual() {
+        let settings = Settings::default(vec![(-5.0, 5.0); 3], sphere);
+        let pop_size = settings.pop_size;
+        let mut pop = Population::new(settings);
+
+        pop.eval_generation();
+
+        assert_eq!(pop.num_cost_evaluations(), pop_size);
+        assert!(pop.best().is_some());
+    }
+
+    #[test]
+    fn eval_generation_only_reevaluates_individuals_with_no_cost() {
+        let settings = Settings::default(vec![(-5.0, 5.0); 3], sphere);
+        let pop_size = settings.pop_size;
+        let mut pop = Population::new(settings);
+
+        pop.eval_generation();
+        assert_eq!(pop.num_cost_evaluations(), pop_size);
+
+        // right after a generation boundary, every individual's cost was
+        // just reset to None by update_positions. Evaluate one of them
+        // serially; the next call must skip it and score only the rest.
+        pop.eval();
+        assert_eq!(pop.num_cost_evaluations(), pop_size + 1);
+        pop.eval_generation();
+        assert_eq!(pop.num_cost_evaluations(), 2 * pop_size);
+    }
+
+    #[test]
+    fn every_strategy_and_crossover_combination_optimizes_sphere() {
+        let strategies = [
+            Strategy::Rand1,
+            Strategy::Best1,
+            Strategy::Rand2,
+            Strategy::CurrentToBest1,
+        ];
+        let crossovers = [Crossover::Binomial, Crossover::Exponential];
+
+        for &strategy in &strategies {
+            for &crossover in &crossovers {
+                let mut settings = Settings::default(vec![(-5.0, 5.0); 3], sphere);
+                settings.strategy = strategy;
+                settings.crossover = crossover;
+                let mut de = Population::new(settings);
+
+                let start_cost = de.iter().next().unwrap();
+                let end_cost = de.iter().nth(2000).unwrap();
+
+                assert!(
+                    end_cost < start_cost,
+                    "{:?}/{:?} did not improve: start={}, end={}",
+                    strategy,
+                    crossover,
+                    start_cost,
+                    end_cost
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn jade_adaptation_optimizes_sphere_and_adapts_mu() {
+        let mut settings = Settings::default(vec![(-5.0, 5.0); 3], sphere);
+        settings.adaptation = Adaptation::Jade { p: 0.1, c: 0.1 };
+        let mut de = Population::new(settings);
+
+        let start_cost = de.iter().next().unwrap();
+        let end_cost = de.iter().nth(2000).unwrap();
+
+        assert!(
+            end_cost < start_cost,
+            "JADE did not improve: start={}, end={}",
+            start_cost,
+            end_cost
+        );
+        // mu_cr/mu_f start at 0.5; after many generations of successful
+        // trials they should have moved away from the initial value.
+        assert!(de.mu_cr != 0.5 || de.mu_f != 0.5);
+        // the archive is capped at the population size.
+        assert!(de.archive.len() <= de.curr.len());
+    }
+
+    #[test]
+    fn bound_handling_clamp_snaps_to_nearest_edge() {
+        let mut rng = XorShiftRng::seed_from_u64(0);
+        assert_eq!(
+            apply_bound_handling(BoundHandling::Clamp, (-1.0, 1.0), 2.0, &mut rng),
+            1.0
+        );
+        assert_eq!(
+            apply_bound_handling(BoundHandling::Clamp, (-1.0, 1.0), -2.0, &mut rng),
+            -1.0
+        );
+    }
+
+    #[test]
+    fn bound_handling_reflect_mirrors_overshoot_back_inside() {
+        let mut rng = XorShiftRng::seed_from_u64(0);
+        let x = apply_bound_handling(BoundHandling::Reflect, (-1.0, 1.0), 1.5, &mut rng);
+        assert_eq!(x, 0.5);
+        let x = apply_bound_handling(BoundHandling::Reflect, (-1.0, 1.0), -1.5, &mut rng);
+        assert_eq!(x, -0.5);
+    }
+
+    #[test]
+    fn bound_handling_wrap_maps_modulo_interval_width() {
+        let mut rng = XorShiftRng::seed_from_u64(0);
+        let x = apply_bound_handling(BoundHandling::Wrap, (-1.0, 1.0), 1.5, &mut rng);
+        assert_eq!(x, -0.5);
+        let x = apply_bound_handling(BoundHandling::Wrap, (-1.0, 1.0), -1.5, &mut rng);
+        assert_eq!(x, 0.5);
+    }
+
+    #[test]
+    fn bound_handling_reinitialize_redraws_within_bounds() {
+        let mut rng = XorShiftRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let x = apply_bound_handling(BoundHandling::Reinitialize, (-1.0, 1.0), 5.0, &mut rng);
+            assert!((-1.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn bound_handling_none_leaves_overshoot_untouched() {
+        let mut rng = XorShiftRng::seed_from_u64(0);
+        assert_eq!(
+            apply_bound_handling(BoundHandling::None, (-1.0, 1.0), 5.0, &mut rng),
+            5.0
+        );
+    }
+
+    #[test]
+    fn restart_triggers_after_configured_stall_generations() {
+        let mut settings = Settings::default(vec![(-5.0, 5.0); 2], sphere);
+        settings.pop_size = 10;
+        settings.restart = Some(RestartConfig {
+            has_improved: Box::new(|_prev: &f32, _curr: &f32| false),
+            stall_generations: 2,
+        });
+        let mut de = Population::new(settings);
+
+        // 10 generations' worth of evaluations: with has_improved always
+        // false and a stall threshold of 2, this must restart at least once.
+        de.iter().nth(10 * 10);
+
+        assert!(de.num_restarts() >= 1);
+    }
+
+    #[test]
+    fn restart_defaults_to_disabled() {
+        let settings = Settings::default(vec![(-5.0, 5.0); 2], sphere);
+        let mut de = Population::new(settings);
+
+        de.iter().nth(500);
+
+        assert_eq!(de.num_restarts(), 0);
+    }
+
+    #[test]
+    fn individuals_reports_one_entry_per_population_member() {
+        let settings = Settings::default(vec![(-5.0, 5.0); 2], sphere);
+        let pop_size = settings.pop_size;
+        let mut de = Population::new(settings);
+
+        // before any evaluation, no individual has a cost yet.
+        assert!(de.individuals().all(|(cost, _, _, _)| cost.is_none()));
+        assert_eq!(de.individuals().count(), pop_size);
+
+        de.eval_generation();
+
+        assert!(de.individuals().all(|(cost, _, _, _)| cost.is_some()));
+    }
+
+    #[test]
+    fn diversity_shrinks_as_population_converges() {
+        let settings = Settings::default(vec![(-5.0, 5.0); 3], sphere);
+        let mut de = Population::new(settings);
+
+        let initial_diversity = de.diversity();
+        de.iter().nth(5000);
+        let final_diversity = de.diversity();
+
+        assert!(initial_diversity > 0.0);
+        assert!(final_diversity < initial_diversity);
+    }
 }